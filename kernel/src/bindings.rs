@@ -1,39 +1,253 @@
 //! Python Bindings for Invariant Kernel
 
+// pyo3 0.20's `#[pymethods]`/`#[pymodule]` expand to `impl`s nested inside a
+// generated function, which trips `non_local_definitions` on newer rustc;
+// the lint is about the macro expansion, not this code, and is fixed in
+// later pyo3 — see https://github.com/PyO3/pyo3/issues/3848.
+#![allow(non_local_definitions)]
+
+use std::path::Path;
+
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 
 // ============================================================================
 // MERKLE: Canonical Identity
 // ============================================================================
 
-/// Get canonical Merkle hash for a token/string
+/// Get canonical Merkle hash for a token/string.
+/// `backend` selects the hash family: "sha256" (default), "blake2b", "sha256d".
+#[pyfunction]
+#[pyo3(signature = (s, backend="sha256"))]
+fn get_token_hash_hex(s: &str, backend: &str) -> PyResult<String> {
+    match backend {
+        "sha256" => Ok(crate::merkle::get_token_hash_hex::<crate::merkle::Sha256Backend>(s)),
+        "blake2b" => Ok(crate::merkle::get_token_hash_hex::<crate::merkle::Blake2bBackend>(s)),
+        "sha256d" => Ok(crate::merkle::get_token_hash_hex::<crate::merkle::Sha256dBackend>(s)),
+        other => Err(PyValueError::new_err(format!("unknown hash backend: {other}"))),
+    }
+}
+
+/// Get the raw 32 bytes of the canonical Merkle hash for a token/string.
+/// `backend` selects the hash family: "sha256" (default), "blake2b", "sha256d".
+#[pyfunction]
+#[pyo3(signature = (s, backend="sha256"))]
+fn get_token_hash_bytes(s: &str, backend: &str) -> PyResult<Vec<u8>> {
+    match backend {
+        "sha256" => Ok(crate::merkle::get_token_hash::<crate::merkle::Sha256Backend>(s).to_byte_array().to_vec()),
+        "blake2b" => Ok(crate::merkle::get_token_hash::<crate::merkle::Blake2bBackend>(s).to_byte_array().to_vec()),
+        "sha256d" => Ok(crate::merkle::get_token_hash::<crate::merkle::Sha256dBackend>(s).to_byte_array().to_vec()),
+        other => Err(PyValueError::new_err(format!("unknown hash backend: {other}"))),
+    }
+}
+
+/// Get edge identity (first 16 chars of Hash(u:rel:v)).
+/// `backend` selects the hash family: "sha256" (default), "blake2b", "sha256d".
+#[pyfunction]
+#[pyo3(signature = (u, v, rel, backend="sha256"))]
+fn bond_id(u: &str, v: &str, rel: &str, backend: &str) -> PyResult<String> {
+    match backend {
+        "sha256" => Ok(crate::merkle::bond_id_hex::<crate::merkle::Sha256Backend>(u, v, rel)),
+        "blake2b" => Ok(crate::merkle::bond_id_hex::<crate::merkle::Blake2bBackend>(u, v, rel)),
+        "sha256d" => Ok(crate::merkle::bond_id_hex::<crate::merkle::Sha256dBackend>(u, v, rel)),
+        other => Err(PyValueError::new_err(format!("unknown hash backend: {other}"))),
+    }
+}
+
+/// Get the raw 8 bytes of the edge identity for (u, rel, v).
+/// `backend` selects the hash family: "sha256" (default), "blake2b", "sha256d".
+#[pyfunction]
+#[pyo3(signature = (u, v, rel, backend="sha256"))]
+fn bond_id_bytes(u: &str, v: &str, rel: &str, backend: &str) -> PyResult<Vec<u8>> {
+    match backend {
+        "sha256" => Ok(crate::merkle::bond_id::<crate::merkle::Sha256Backend>(u, v, rel).to_byte_array().to_vec()),
+        "blake2b" => Ok(crate::merkle::bond_id::<crate::merkle::Blake2bBackend>(u, v, rel).to_byte_array().to_vec()),
+        "sha256d" => Ok(crate::merkle::bond_id::<crate::merkle::Sha256dBackend>(u, v, rel).to_byte_array().to_vec()),
+        other => Err(PyValueError::new_err(format!("unknown hash backend: {other}"))),
+    }
+}
+
+/// Build an inclusion proof for the byte at `index` in `s`.
+/// Returns `(left_sibling_hexes, tail_hex)`.
+#[pyfunction]
+fn token_proof(s: &str, index: usize) -> PyResult<(Vec<String>, String)> {
+    if index >= s.len() {
+        return Err(PyValueError::new_err(format!(
+            "index {index} out of bounds for a {}-byte string",
+            s.len()
+        )));
+    }
+    let proof = crate::merkle::token_proof(s, index);
+    Ok((
+        proof.left_siblings.iter().map(hex::encode).collect(),
+        hex::encode(proof.tail),
+    ))
+}
+
+/// Verify a proof produced by `token_proof` against a root hash.
+#[pyfunction]
+fn verify_token_proof(
+    root_hex: &str,
+    index: usize,
+    byte: u8,
+    left_siblings: Vec<String>,
+    tail_hex: &str,
+) -> bool {
+    let left_siblings: Option<Vec<[u8; 32]>> = left_siblings
+        .iter()
+        .map(|h| hex::decode(h).ok().and_then(|v| v.try_into().ok()))
+        .collect();
+    let tail: Option<[u8; 32]> = hex::decode(tail_hex).ok().and_then(|v| v.try_into().ok());
+
+    match (left_siblings, tail) {
+        (Some(left_siblings), Some(tail)) => crate::merkle::verify_token_proof(
+            root_hex,
+            index,
+            byte,
+            &crate::merkle::TokenProof { left_siblings, tail },
+        ),
+        _ => false,
+    }
+}
+
+/// Parse a hex-encoded bond id, as produced by `bond_id`.
+fn parse_bond_id(hex_str: &str) -> PyResult<crate::merkle::BondId> {
+    hex_str
+        .parse()
+        .map_err(|_| PyValueError::new_err(format!("invalid bond id: {hex_str}")))
+}
+
+/// Canonical Merkle root over a set of bond ids (a crystallized graph).
+#[pyfunction]
+fn graph_root(bonds: Vec<String>) -> PyResult<String> {
+    let bonds: Vec<crate::merkle::BondId> =
+        bonds.iter().map(|b| parse_bond_id(b)).collect::<PyResult<_>>()?;
+    Ok(crate::merkle::graph_root(&bonds))
+}
+
+/// Sibling path proving `target` is part of the graph committed to by
+/// `graph_root`. Returns `None` if `target` is not among `bonds`.
+/// Each entry is `(sibling_hash_hex, sibling_is_left)`.
 #[pyfunction]
-fn get_token_hash_hex(s: &str) -> String {
-    crate::merkle::get_token_hash_hex(s)
+fn graph_proof(bonds: Vec<String>, target: &str) -> PyResult<Option<Vec<(String, bool)>>> {
+    let bonds: Vec<crate::merkle::BondId> =
+        bonds.iter().map(|b| parse_bond_id(b)).collect::<PyResult<_>>()?;
+    let target = parse_bond_id(target)?;
+
+    Ok(crate::merkle::graph_proof(&bonds, target).map(|path| {
+        path.into_iter()
+            .map(|(sibling, is_left)| (hex::encode(sibling), is_left))
+            .collect()
+    }))
 }
 
-/// Get edge identity (first 16 chars of SHA256(u:rel:v))
+/// Verify a proof produced by `graph_proof` against a graph root.
 #[pyfunction]
-fn bond_id(u: &str, v: &str, rel: &str) -> String {
-    crate::merkle::bond_id(u, v, rel)
+fn verify_graph_proof(root_hex: &str, target_bond_id: &str, path: Vec<(String, bool)>) -> PyResult<bool> {
+    let target = parse_bond_id(target_bond_id)?;
+    let path: Option<Vec<([u8; 32], bool)>> = path
+        .into_iter()
+        .map(|(h, is_left)| {
+            hex::decode(h)
+                .ok()
+                .and_then(|v| v.try_into().ok())
+                .map(|sibling| (sibling, is_left))
+        })
+        .collect();
+
+    match path {
+        Some(path) => Ok(crate::merkle::verify_graph_proof(root_hex, target, &path)),
+        None => Ok(false),
+    }
 }
 
 // ============================================================================
 // CRYSTALLIZE: Vector Similarity
 // ============================================================================
 
-/// Brute-force all-pairs cosine similarity (O(N²))
+/// Brute-force all-pairs similarity (O(N²)).
+/// `metric` selects the scoring function: "cosine" (default), "dot",
+/// "euclidean", "angular".
 #[pyfunction]
-fn crystallize_all(vectors: Vec<Vec<f32>>, threshold: f32) -> Vec<(usize, usize, f32)> {
-    let results = crate::start_crystal::compute_correlations_parallel(&vectors, threshold);
-    results.into_iter().map(|r| (r.source_idx, r.target_idx, r.score)).collect()
+#[pyo3(signature = (vectors, threshold, metric="cosine"))]
+fn crystallize_all(vectors: Vec<Vec<f32>>, threshold: f32, metric: &str) -> PyResult<Vec<(usize, usize, f32)>> {
+    let metric: crate::metric::Metric = metric
+        .parse()
+        .map_err(|_| PyValueError::new_err(format!("unknown metric: {metric}")))?;
+    let results = crate::start_crystal::compute_correlations_parallel(&vectors, threshold, metric);
+    Ok(results.into_iter().map(|r| (r.source_idx, r.target_idx, r.score)).collect())
 }
 
-/// HNSW approximate nearest neighbors (O(N log N))
+/// HNSW approximate nearest neighbors (O(N log N)).
+/// `metric` selects the scoring function: "cosine" (default), "dot",
+/// "euclidean", "angular".
 #[pyfunction]
-fn crystallize_hnsw(vectors: Vec<Vec<f32>>, threshold: f32, top_k: usize) -> Vec<(usize, usize, f32)> {
-    let results = crate::hnsw_crystal::crystallize_hnsw(&vectors, threshold, top_k);
-    results.into_iter().map(|r| (r.source_idx, r.target_idx, r.score)).collect()
+#[pyo3(signature = (vectors, threshold, top_k, metric="cosine"))]
+fn crystallize_hnsw(vectors: Vec<Vec<f32>>, threshold: f32, top_k: usize, metric: &str) -> PyResult<Vec<(usize, usize, f32)>> {
+    let metric: crate::metric::Metric = metric
+        .parse()
+        .map_err(|_| PyValueError::new_err(format!("unknown metric: {metric}")))?;
+    let results = crate::hnsw_crystal::crystallize_hnsw(&vectors, threshold, top_k, metric);
+    Ok(results.into_iter().map(|r| (r.source_idx, r.target_idx, r.score)).collect())
+}
+
+/// Persistent, incrementally-updatable HNSW similarity index.
+///
+/// Unlike `crystallize_hnsw`, which rebuilds the whole graph on every call,
+/// keeps a live index that grows vector-by-vector and can be reused or
+/// persisted across processes.
+#[pyclass]
+struct CrystalIndex {
+    inner: crate::hnsw_crystal::CrystalIndex,
+}
+
+#[pymethods]
+impl CrystalIndex {
+    #[new]
+    #[pyo3(signature = (expected_capacity=1000))]
+    fn new(expected_capacity: usize) -> Self {
+        Self { inner: crate::hnsw_crystal::CrystalIndex::new(expected_capacity) }
+    }
+
+    /// Add a batch of vectors, returning the id assigned to each, in order.
+    fn add(&mut self, vectors: Vec<Vec<f32>>) -> Vec<usize> {
+        self.inner.add(vectors)
+    }
+
+    /// Add a single vector, returning its id within the index.
+    fn add_one(&mut self, vector: Vec<f32>) -> usize {
+        self.inner.add_one(vector)
+    }
+
+    /// Query the index for neighbors of `vector` scoring above `threshold`.
+    fn query(&self, vector: Vec<f32>, top_k: usize, threshold: f32) -> Vec<(usize, usize, f32)> {
+        self.inner
+            .query(&vector, top_k, threshold)
+            .into_iter()
+            .map(|r| (r.source_idx, r.target_idx, r.score))
+            .collect()
+    }
+
+    /// Get the neighbors of an already-indexed vector `id`.
+    fn neighbors(&self, id: usize, top_k: usize, threshold: f32) -> Vec<(usize, usize, f32)> {
+        self.inner
+            .neighbors(id, top_k, threshold)
+            .into_iter()
+            .map(|r| (r.source_idx, r.target_idx, r.score))
+            .collect()
+    }
+
+    /// Persist the index to disk at `path`.
+    fn save(&self, path: &str) -> PyResult<()> {
+        self.inner.save(Path::new(path)).map_err(PyValueError::new_err)
+    }
+
+    /// Load a previously-saved index from disk at `path`.
+    #[staticmethod]
+    fn load(path: &str) -> PyResult<Self> {
+        let inner = crate::hnsw_crystal::CrystalIndex::load(Path::new(path)).map_err(PyValueError::new_err)?;
+        Ok(Self { inner })
+    }
 }
 
 // ============================================================================
@@ -54,11 +268,19 @@ fn get_invariant_metrics(s: &str, atomic: bool) -> (u32, u32, u32, u64) {
 fn invariant_kernel(_py: Python, m: &PyModule) -> PyResult<()> {
     // Merkle
     m.add_function(wrap_pyfunction!(get_token_hash_hex, m)?)?;
+    m.add_function(wrap_pyfunction!(get_token_hash_bytes, m)?)?;
     m.add_function(wrap_pyfunction!(bond_id, m)?)?;
+    m.add_function(wrap_pyfunction!(bond_id_bytes, m)?)?;
+    m.add_function(wrap_pyfunction!(token_proof, m)?)?;
+    m.add_function(wrap_pyfunction!(verify_token_proof, m)?)?;
+    m.add_function(wrap_pyfunction!(graph_root, m)?)?;
+    m.add_function(wrap_pyfunction!(graph_proof, m)?)?;
+    m.add_function(wrap_pyfunction!(verify_graph_proof, m)?)?;
     m.add_function(wrap_pyfunction!(get_invariant_metrics, m)?)?;
     // Crystallize
     m.add_function(wrap_pyfunction!(crystallize_all, m)?)?;
     m.add_function(wrap_pyfunction!(crystallize_hnsw, m)?)?;
+    m.add_class::<CrystalIndex>()?;
     Ok(())
 }
 
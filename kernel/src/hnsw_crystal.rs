@@ -1,8 +1,15 @@
 //! HNSW crystallization (O(N log N))
 
+use std::fs;
+use std::path::{Path, PathBuf};
+
 use rayon::prelude::*;
+use hnsw_rs::api::AnnT;
 use hnsw_rs::hnsw::Hnsw;
-use hnsw_rs::dist::DistCosine;
+use hnsw_rs::dist::{Distance, DistCosine, DistDot, DistL2};
+use hnsw_rs::hnswio::HnswIo;
+
+use crate::metric::Metric;
 
 pub struct EdgeResult {
     pub source_idx: usize,
@@ -10,41 +17,66 @@ pub struct EdgeResult {
     pub score: f32,
 }
 
-/// Crystallize using HNSW Index (Approximate Nearest Neighbors).
+/// Crystallize using HNSW Index (Approximate Nearest Neighbors) under the
+/// given metric.
+///
+/// The HNSW distance type only shapes which candidates the graph search
+/// finds; every candidate's final score is recomputed with the shared
+/// [`Metric::score`], so the threshold means exactly the same thing here as
+/// it does in [`crate::start_crystal::compute_correlations_parallel`].
 pub fn crystallize_hnsw(
-    vectors: &[Vec<f32>], 
+    vectors: &[Vec<f32>],
     threshold: f32,
-    top_k: usize
+    top_k: usize,
+    metric: Metric,
 ) -> Vec<EdgeResult> {
     if vectors.is_empty() { return vec![]; }
+    match metric {
+        Metric::Cosine => crystallize_hnsw_generic::<DistCosine>(vectors, threshold, top_k, metric),
+        Metric::DotProduct => crystallize_hnsw_generic::<DistDot>(vectors, threshold, top_k, metric),
+        Metric::Euclidean => crystallize_hnsw_generic::<DistL2>(vectors, threshold, top_k, metric),
+        // hnsw_rs has no native angular distance type; cosine ranks
+        // candidates identically (angular similarity is a monotonic
+        // transform of cosine similarity), and the exact angular score is
+        // recomputed below regardless.
+        Metric::Angular => crystallize_hnsw_generic::<DistCosine>(vectors, threshold, top_k, metric),
+    }
+}
+
+fn crystallize_hnsw_generic<D: Distance<f32> + Default + Send + Sync>(
+    vectors: &[Vec<f32>],
+    threshold: f32,
+    top_k: usize,
+    metric: Metric,
+) -> Vec<EdgeResult> {
     let nb_elem = vectors.len();
-    
+
     let max_nb_connection = 16;
     let nb_layer = 16.min((nb_elem as f32).ln().ceil() as usize);
     let ef_construction = 200;
-    
-    let hnsw: Hnsw<f32, DistCosine> = Hnsw::new(
-        max_nb_connection, nb_elem, nb_layer, ef_construction, DistCosine {},
+
+    let hnsw: Hnsw<f32, D> = Hnsw::new(
+        max_nb_connection, nb_elem, nb_layer, ef_construction, D::default(),
     );
-    
+
     let data_for_insert: Vec<(&Vec<f32>, usize)> = vectors.iter()
         .enumerate()
         .map(|(i, v)| (v, i))
         .collect();
-    
+
     hnsw.parallel_insert(&data_for_insert);
-    
+
     let ef_search = 32.max(top_k);
     let search_k = top_k + 1;
-    
+
     vectors.par_iter().enumerate().flat_map(|(i, query_vec)| {
         hnsw.search(query_vec, search_k, ef_search)
             .into_iter()
             .filter_map(|neighbor| {
                 let j = neighbor.d_id;
-                let sim = 1.0 - neighbor.distance;
-                if i != j && sim > threshold {
-                    Some(EdgeResult { source_idx: i, target_idx: j, score: sim })
+                let score = metric.score(query_vec, &vectors[j]);
+                if i != j && score > threshold {
+                    Some(EdgeResult { source_idx: i, target_idx: j, score })
                 } else {
                     None
                 }
@@ -52,3 +84,203 @@ pub fn crystallize_hnsw(
             .collect::<Vec<_>>()
     }).collect()
 }
+
+/// Persistent, incrementally-updatable HNSW similarity index.
+///
+/// Unlike [`crystallize_hnsw`], which rebuilds the whole graph on every
+/// call, `CrystalIndex` owns a live `Hnsw` so vectors can be added one at a
+/// time and the same graph reused across [`query`](Self::query) /
+/// [`neighbors`](Self::neighbors) calls without O(N²) recomputation, and can
+/// be persisted to disk and reloaded.
+pub struct CrystalIndex {
+    hnsw: Hnsw<'static, f32, DistCosine>,
+    // Each vector is boxed individually so pushing new entries never moves
+    // already-inserted vectors, keeping the 'static references held by
+    // `hnsw` valid for as long as `self` is alive.
+    vectors: Vec<Box<[f32]>>,
+    // `HnswIo::load_hnsw` is bounded `'a: 'b`, so a graph reloaded via
+    // `load()` borrows from the `HnswIo` that produced it. Boxing the
+    // reloader and keeping it alive here (like `vectors` above) lets that
+    // borrow be treated as `'static` for as long as `self` is alive. Only
+    // ever `Some` for an index obtained from `load()`.
+    _reloader: Option<Box<HnswIo>>,
+}
+
+impl CrystalIndex {
+    /// Create an empty index sized for roughly `expected_capacity` vectors.
+    pub fn new(expected_capacity: usize) -> Self {
+        let max_nb_connection = 16;
+        let nb_layer = 16.min((expected_capacity.max(1) as f32).ln().ceil() as usize);
+        let ef_construction = 200;
+        let hnsw = Hnsw::new(
+            max_nb_connection,
+            expected_capacity.max(1),
+            nb_layer,
+            ef_construction,
+            DistCosine {},
+        );
+        Self { hnsw, vectors: Vec::new(), _reloader: None }
+    }
+
+    /// Add a batch of vectors, returning the id assigned to each, in order.
+    pub fn add(&mut self, vectors: Vec<Vec<f32>>) -> Vec<usize> {
+        vectors.into_iter().map(|v| self.add_one(v)).collect()
+    }
+
+    /// Add a single vector, returning its id within the index.
+    pub fn add_one(&mut self, vector: Vec<f32>) -> usize {
+        let id = self.vectors.len();
+        self.vectors.push(vector.into_boxed_slice());
+
+        // SAFETY: `self.vectors[id]` is a separately heap-allocated boxed
+        // slice that never moves or is freed while `self` is alive; growing
+        // `self.vectors` only reallocates the outer `Vec`, not the boxed
+        // slice contents it points to.
+        let data: &'static [f32] = unsafe { std::mem::transmute(&*self.vectors[id]) };
+        self.hnsw.insert_slice((data, id));
+        id
+    }
+
+    /// Query the index for neighbors of an arbitrary `vector`, scoring
+    /// above `threshold`. `source_idx` is `usize::MAX` since the query
+    /// vector has no id of its own in the index.
+    pub fn query(&self, vector: &[f32], top_k: usize, threshold: f32) -> Vec<EdgeResult> {
+        let ef_search = 32.max(top_k);
+        self.hnsw
+            .search(vector, top_k, ef_search)
+            .into_iter()
+            .filter_map(|neighbor| {
+                let sim = 1.0 - neighbor.distance;
+                if sim > threshold {
+                    Some(EdgeResult { source_idx: usize::MAX, target_idx: neighbor.d_id, score: sim })
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Get the neighbors of an already-indexed vector `id`. Returns an empty
+    /// list if `id` has no vector data (e.g. a graph loaded via [`load`](Self::load)
+    /// whose vector sidecar file was missing).
+    pub fn neighbors(&self, id: usize, top_k: usize, threshold: f32) -> Vec<EdgeResult> {
+        if id >= self.vectors.len() {
+            return vec![];
+        }
+        let ef_search = 32.max(top_k);
+        let search_k = top_k + 1;
+        self.hnsw
+            .search(&self.vectors[id], search_k, ef_search)
+            .into_iter()
+            .filter_map(|neighbor| {
+                let j = neighbor.d_id;
+                let sim = 1.0 - neighbor.distance;
+                if id != j && sim > threshold {
+                    Some(EdgeResult { source_idx: id, target_idx: j, score: sim })
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Sidecar path storing the raw vector data next to a graph dump at
+    /// `path`, so a reload can restore `self.vectors` (and therefore keep
+    /// assigning ids past the loaded graph's highest `DataId`).
+    fn vectors_sidecar_path(path: &Path) -> PathBuf {
+        let mut file_name = path.file_name().map(|n| n.to_os_string()).unwrap_or_default();
+        file_name.push(".vecs");
+        path.with_file_name(file_name)
+    }
+
+    /// Persist the index's graph to disk at `path` (parent directory +
+    /// file name used as the dump basename), along with a sidecar file
+    /// holding the raw vector data so a subsequent [`load`](Self::load) can
+    /// keep assigning unique ids and answer [`neighbors`](Self::neighbors).
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let directory = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or_else(|| Path::new("."));
+        let basename = path.file_name().and_then(|n| n.to_str()).ok_or("invalid path")?.to_string();
+
+        // `Hnsw::file_dump` (via the `AnnT` trait) always dumps relative to
+        // the process's current directory, ignoring any directory of its
+        // own — so the only way to honor an arbitrary `directory` here is
+        // to dump from inside it and restore the previous directory after.
+        let previous_dir = std::env::current_dir().map_err(|e| e.to_string())?;
+        std::env::set_current_dir(directory).map_err(|e| e.to_string())?;
+        let dump_result = self.hnsw.file_dump(&basename).map_err(|e| e.to_string());
+        std::env::set_current_dir(&previous_dir).map_err(|e| e.to_string())?;
+        dump_result?;
+
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&(self.vectors.len() as u64).to_le_bytes());
+        for vector in &self.vectors {
+            buf.extend_from_slice(&(vector.len() as u64).to_le_bytes());
+            for component in vector.iter() {
+                buf.extend_from_slice(&component.to_le_bytes());
+            }
+        }
+        fs::write(Self::vectors_sidecar_path(path), buf).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Load a previously-saved index's graph (and its vector sidecar) from
+    /// disk at `path`.
+    ///
+    /// Ids assigned by later [`add`](Self::add)/[`add_one`](Self::add_one)
+    /// calls continue from the restored vector count, so they never collide
+    /// with the `DataId`s already present in the loaded graph.
+    ///
+    /// If the sidecar is missing (e.g. a graph saved before this field
+    /// existed), the index loads with no vectors: ids then restart at 0 on
+    /// the next `add`/`add_one`, which *will* collide with the loaded
+    /// graph's ids, and [`neighbors`](Self::neighbors) returns an empty list
+    /// for every id until vectors are re-added. Saving such an index writes
+    /// a sidecar holding only the vectors added since the load.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let directory = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or_else(|| Path::new("."));
+        let basename = path.file_name().and_then(|n| n.to_str()).ok_or("invalid path")?;
+        let mut reloader = Box::new(HnswIo::new(directory.to_path_buf(), basename.to_string()));
+
+        // SAFETY: `load_hnsw` is bounded `'a: 'b`, so the returned `Hnsw`
+        // borrows `reloader` for as long as that borrow lives. `reloader`
+        // is heap-allocated and moves into `self._reloader`, kept alive for
+        // the lifetime of `self`, so treating this mutable borrow as
+        // `'static` is sound: the data it points at never moves or is freed
+        // while the resulting `Hnsw` is reachable.
+        let reloader_ref: &'static mut HnswIo = unsafe { std::mem::transmute(&mut *reloader) };
+        let hnsw: Hnsw<'static, f32, DistCosine> =
+            reloader_ref.load_hnsw().map_err(|e| e.to_string())?;
+
+        let vectors = match fs::read(Self::vectors_sidecar_path(path)) {
+            Ok(buf) => Self::decode_vectors(&buf)?,
+            Err(_) => Vec::new(),
+        };
+        Ok(Self { hnsw, vectors, _reloader: Some(reloader) })
+    }
+
+    fn decode_vectors(buf: &[u8]) -> Result<Vec<Box<[f32]>>, String> {
+        let read_u64 = |buf: &[u8], offset: usize| -> Result<u64, String> {
+            buf.get(offset..offset + 8)
+                .map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+                .ok_or_else(|| "truncated vector sidecar".to_string())
+        };
+
+        let count = read_u64(buf, 0)? as usize;
+        let mut offset = 8;
+        let mut vectors = Vec::with_capacity(count);
+        for _ in 0..count {
+            let len = read_u64(buf, offset)? as usize;
+            offset += 8;
+            let bytes = buf
+                .get(offset..offset + len * 4)
+                .ok_or_else(|| "truncated vector sidecar".to_string())?;
+            offset += len * 4;
+            let vector: Vec<f32> = bytes
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+                .collect();
+            vectors.push(vector.into_boxed_slice());
+        }
+        Ok(vectors)
+    }
+}
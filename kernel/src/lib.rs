@@ -5,6 +5,7 @@
 //! - Crystallization (vector similarity)
 
 pub mod merkle;
+mod metric;
 mod start_crystal;
 mod hnsw_crystal;
 
@@ -1,72 +1,433 @@
 //! Merkle Hashing — Canonical Topological Identity
 //!
-//! Algorithm: String → Byte Tree → Recursive SHA256(0x00|0x01 + L + R)
+//! Algorithm: String → Byte Tree → Recursive Hash(0x00|0x01 + L + R)
 //! This is the single source of truth for identity computation.
+//!
+//! The hash function family itself is pluggable via [`HashBackend`] so
+//! callers can trade SHA-256's ubiquity for BLAKE2b's throughput or
+//! SHA-256d's length-extension resistance without touching the tree shape.
+
+use sha2::{Sha256, Digest as Sha2Digest};
+use blake2::Blake2b;
+use blake2::digest::consts::U32;
+
+/// A pluggable hash function family for Merkle tree construction.
+///
+/// Every backend other than the untagged default [`Sha256Backend`] mixes
+/// its own fixed domain tag into [`hash_origin`] and [`hash_bytes`], so the
+/// same input produces a different root under a different backend —
+/// identities never collide across backends.
+///
+/// [`hash_origin`]: HashBackend::hash_origin
+/// [`hash_bytes`]: HashBackend::hash_bytes
+pub trait HashBackend {
+    /// Hash of Origin (Ω).
+    fn hash_origin() -> [u8; 32];
+    /// Hash of Dyad Δ(left, right).
+    fn hash_dyad(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32];
+    /// Hash of an arbitrary byte string (used by [`bond_id`]).
+    fn hash_bytes(data: &[u8]) -> [u8; 32];
+}
+
+/// Default backend: plain SHA-256, untagged. This is the original hash
+/// family the crate has always used, kept bit-for-bit unchanged so existing
+/// identities computed before backends existed remain valid.
+pub struct Sha256Backend;
+
+impl HashBackend for Sha256Backend {
+    fn hash_origin() -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update([0x00]);
+        hasher.finalize().into()
+    }
+
+    fn hash_dyad(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update([0x01]);
+        hasher.update(left);
+        hasher.update(right);
+        hasher.finalize().into()
+    }
+
+    fn hash_bytes(data: &[u8]) -> [u8; 32] {
+        Sha256::digest(data).into()
+    }
+}
+
+/// BLAKE2b-256 backend, domain-tagged with `b"blake2b"`. Significantly
+/// faster than SHA-256 on large corpora.
+pub struct Blake2bBackend;
+
+type Blake2b256 = Blake2b<U32>;
+
+impl HashBackend for Blake2bBackend {
+    fn hash_origin() -> [u8; 32] {
+        let mut hasher = Blake2b256::new();
+        hasher.update(b"blake2b");
+        hasher.update([0x00]);
+        hasher.finalize().into()
+    }
+
+    fn hash_dyad(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+        let mut hasher = Blake2b256::new();
+        hasher.update(b"blake2b");
+        hasher.update([0x01]);
+        hasher.update(left);
+        hasher.update(right);
+        hasher.finalize().into()
+    }
+
+    fn hash_bytes(data: &[u8]) -> [u8; 32] {
+        let mut hasher = Blake2b256::new();
+        hasher.update(b"blake2b");
+        hasher.update(data);
+        hasher.finalize().into()
+    }
+}
+
+/// SHA-256d backend (SHA-256 applied twice), domain-tagged with
+/// `b"sha256d"`. Length-extension-resistant, Bitcoin-style.
+pub struct Sha256dBackend;
+
+impl Sha256dBackend {
+    fn sha256d(data: &[u8]) -> [u8; 32] {
+        let first: [u8; 32] = Sha256::digest(data).into();
+        Sha256::digest(first).into()
+    }
+}
+
+impl HashBackend for Sha256dBackend {
+    fn hash_origin() -> [u8; 32] {
+        Self::sha256d(b"sha256d\x00")
+    }
+
+    fn hash_dyad(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+        let mut buf = Vec::with_capacity(8 + 65);
+        buf.extend_from_slice(b"sha256d");
+        buf.push(0x01);
+        buf.extend_from_slice(left);
+        buf.extend_from_slice(right);
+        Self::sha256d(&buf)
+    }
+
+    fn hash_bytes(data: &[u8]) -> [u8; 32] {
+        let mut buf = Vec::with_capacity(7 + data.len());
+        buf.extend_from_slice(b"sha256d");
+        buf.extend_from_slice(data);
+        Self::sha256d(&buf)
+    }
+}
+
+/// A canonical 32-byte token identity, as produced by [`get_token_hash`].
+///
+/// Avoids redundant hex round-trips when an identity needs to be fed back
+/// into hashing (e.g. as a Merkle tree node) rather than displayed, and
+/// keeps callers from mixing up a token hash with a [`BondId`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TokenHash([u8; 32]);
+
+impl TokenHash {
+    /// Wrap a raw 32-byte hash.
+    pub fn from_byte_array(bytes: [u8; 32]) -> Self {
+        TokenHash(bytes)
+    }
+
+    /// Take ownership of the raw bytes.
+    pub fn to_byte_array(self) -> [u8; 32] {
+        self.0
+    }
+
+    /// Borrow the raw bytes.
+    pub fn as_byte_array(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// 64-character hex encoding.
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0)
+    }
+}
+
+impl std::fmt::Display for TokenHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+impl std::fmt::Debug for TokenHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "TokenHash({})", self.to_hex())
+    }
+}
+
+impl std::str::FromStr for TokenHash {
+    type Err = HashParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = hex::decode(s).map_err(|_| HashParseError("invalid hex"))?;
+        let array: [u8; 32] = bytes.try_into().map_err(|_| HashParseError("expected 32 bytes"))?;
+        Ok(TokenHash(array))
+    }
+}
+
+/// A canonical 8-byte bond (edge) identity, as produced by [`bond_id`].
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BondId([u8; 8]);
+
+impl BondId {
+    /// Wrap a raw 8-byte id.
+    pub fn from_byte_array(bytes: [u8; 8]) -> Self {
+        BondId(bytes)
+    }
+
+    /// Take ownership of the raw bytes.
+    pub fn to_byte_array(self) -> [u8; 8] {
+        self.0
+    }
+
+    /// Borrow the raw bytes.
+    pub fn as_byte_array(&self) -> &[u8; 8] {
+        &self.0
+    }
+
+    /// 16-character hex encoding.
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0)
+    }
+}
+
+impl std::fmt::Display for BondId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+impl std::fmt::Debug for BondId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "BondId({})", self.to_hex())
+    }
+}
 
-use sha2::{Sha256, Digest};
+impl std::str::FromStr for BondId {
+    type Err = HashParseError;
 
-/// Hash of Origin (Ω): SHA256(0x00)
-fn hash_origin() -> [u8; 32] {
-    let mut hasher = Sha256::new();
-    hasher.update(&[0x00]);
-    hasher.finalize().into()
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = hex::decode(s).map_err(|_| HashParseError("invalid hex"))?;
+        let array: [u8; 8] = bytes.try_into().map_err(|_| HashParseError("expected 8 bytes"))?;
+        Ok(BondId(array))
+    }
 }
 
-/// Hash of Dyad Δ(left, right): SHA256(0x01 || left || right)
-fn hash_dyad(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
-    let mut hasher = Sha256::new();
-    hasher.update(&[0x01]);
-    hasher.update(left);
-    hasher.update(right);
-    hasher.finalize().into()
+/// Error parsing a [`TokenHash`] or [`BondId`] from a string that isn't
+/// valid hex of the expected length.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HashParseError(&'static str);
+
+impl std::fmt::Display for HashParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.0)
+    }
 }
 
+impl std::error::Error for HashParseError {}
+
 /// Encode a single bit as topology.
 /// 0 -> Origin (Ω)
 /// 1 -> Dyad(Origin, Origin)
-fn encode_bit(bit: u8) -> [u8; 32] {
+fn encode_bit<B: HashBackend>(bit: u8) -> [u8; 32] {
     if bit == 0 {
-        hash_origin()
+        B::hash_origin()
     } else {
-        hash_dyad(&hash_origin(), &hash_origin())
+        B::hash_dyad(&B::hash_origin(), &B::hash_origin())
     }
 }
 
 /// Encode a byte as 8-depth binary tree (LSB first).
-fn encode_byte(byte_val: u8) -> [u8; 32] {
-    let mut chain = hash_origin();
+fn encode_byte<B: HashBackend>(byte_val: u8) -> [u8; 32] {
+    let mut chain = B::hash_origin();
     for i in 0..8 {
         let bit = (byte_val >> i) & 1;
-        let bit_node = encode_bit(bit);
-        chain = hash_dyad(&bit_node, &chain);
+        let bit_node = encode_bit::<B>(bit);
+        chain = B::hash_dyad(&bit_node, &chain);
     }
     chain
 }
 
 /// Encode string as Chain of Byte Trees (reversed for cons-list).
-fn encode_string(s: &[u8]) -> [u8; 32] {
-    let mut chain = hash_origin();
+fn encode_string<B: HashBackend>(s: &[u8]) -> [u8; 32] {
+    let mut chain = B::hash_origin();
     for &b in s.iter().rev() {
-        let byte_tree = encode_byte(b);
-        chain = hash_dyad(&byte_tree, &chain);
+        let byte_tree = encode_byte::<B>(b);
+        chain = B::hash_dyad(&byte_tree, &chain);
     }
     chain
 }
 
-/// Canonical Identity Function for Tokens.
-/// Returns 64-character hex string.
-pub fn get_token_hash_hex(s: &str) -> String {
-    let root = encode_string(s.as_bytes());
-    hex::encode(root)
+/// Canonical Identity Function for Tokens, under a chosen hash backend.
+pub fn get_token_hash<B: HashBackend>(s: &str) -> TokenHash {
+    TokenHash(encode_string::<B>(s.as_bytes()))
+}
+
+/// Hex-encoded canonical identity for tokens. Thin wrapper over
+/// [`get_token_hash`] for callers that want a display string rather than
+/// raw bytes.
+pub fn get_token_hash_hex<B: HashBackend>(s: &str) -> String {
+    get_token_hash::<B>(s).to_hex()
+}
+
+/// Merkle inclusion proof that a given byte sits at a given index of a
+/// string's token hash tree, without revealing the rest of the string.
+///
+/// `root = Δ(T₀, Δ(T₁, … Δ(T_{n-1}, Ω)…))`, so proving byte `k` needs the
+/// ordered byte-tree hashes `T₀..T_{k-1}` (left siblings on the way down to
+/// depth k) and the single hash of the tail chain to the right of `T_k`.
+pub struct TokenProof {
+    /// Hashes of byte-trees `T₀..T_{k-1}`, in index order.
+    pub left_siblings: Vec<[u8; 32]>,
+    /// Hash of the tail chain `Δ(T_{k+1}, …, Ω)`.
+    pub tail: [u8; 32],
+}
+
+/// Build an inclusion proof for the byte at `index` in `s`, under the
+/// default [`Sha256Backend`].
+///
+/// Panics if `index` is out of bounds, mirroring indexing on `&str`/`[u8]`.
+pub fn token_proof(s: &str, index: usize) -> TokenProof {
+    let bytes = s.as_bytes();
+    assert!(index < bytes.len(), "index out of bounds");
+
+    let mut tail = Sha256Backend::hash_origin();
+    for &b in bytes[index + 1..].iter().rev() {
+        tail = Sha256Backend::hash_dyad(&encode_byte::<Sha256Backend>(b), &tail);
+    }
+
+    let left_siblings = bytes[..index]
+        .iter()
+        .map(|&b| encode_byte::<Sha256Backend>(b))
+        .collect();
+
+    TokenProof { left_siblings, tail }
 }
 
-/// Edge Identity: First 16 chars of SHA256(u:rel:v).
-pub fn bond_id(u: &str, v: &str, rel: &str) -> String {
+/// Verify a [`TokenProof`] that `byte` sits at `index` under `root_hex`,
+/// computed with the default [`Sha256Backend`].
+pub fn verify_token_proof(root_hex: &str, index: usize, byte: u8, proof: &TokenProof) -> bool {
+    let root = match hex::decode(root_hex) {
+        Ok(bytes) if bytes.len() == 32 => bytes,
+        _ => return false,
+    };
+    if proof.left_siblings.len() != index {
+        return false;
+    }
+
+    let mut chain = Sha256Backend::hash_dyad(&encode_byte::<Sha256Backend>(byte), &proof.tail);
+    for sibling in proof.left_siblings.iter().rev() {
+        chain = Sha256Backend::hash_dyad(sibling, &chain);
+    }
+
+    chain.as_slice() == root.as_slice()
+}
+
+/// Edge Identity: First 8 bytes of `Hash(u:rel:v)` under a chosen backend.
+pub fn bond_id<B: HashBackend>(u: &str, v: &str, rel: &str) -> BondId {
     let raw = format!("{}:{}:{}", u, rel, v);
-    let mut hasher = Sha256::new();
-    hasher.update(raw.as_bytes());
-    let result = hasher.finalize();
-    hex::encode(&result[..8]) // 16 hex chars
+    let hash = B::hash_bytes(raw.as_bytes());
+    let mut array = [0u8; 8];
+    array.copy_from_slice(&hash[..8]);
+    BondId(array)
+}
+
+/// Hex-encoded edge identity (16 hex chars). Thin wrapper over [`bond_id`].
+pub fn bond_id_hex<B: HashBackend>(u: &str, v: &str, rel: &str) -> String {
+    bond_id::<B>(u, v, rel).to_hex()
+}
+
+/// Build one level of a binary Merkle reduction, duplicating the last node
+/// when the level has an odd count.
+fn reduce_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    let mut padded = level.to_vec();
+    if padded.len() % 2 == 1 {
+        padded.push(*padded.last().unwrap());
+    }
+    padded
+        .chunks(2)
+        .map(|pair| Sha256Backend::hash_dyad(&pair[0], &pair[1]))
+        .collect()
+}
+
+/// Canonical Merkle root over a set of bond ids (a crystallized graph).
+///
+/// Bond ids are sorted — so any two machines that produced the same edge
+/// set agree on one commitment — hashed into 32-byte leaves directly from
+/// their raw bytes (no hex round-trip), then reduced pairwise with
+/// [`HashBackend::hash_dyad`], duplicating the last node when a level has an
+/// odd count, down to a single 64-hex-character root.
+pub fn graph_root(bonds: &[BondId]) -> String {
+    let mut sorted: Vec<BondId> = bonds.to_vec();
+    sorted.sort_unstable();
+
+    if sorted.is_empty() {
+        return hex::encode(Sha256Backend::hash_origin());
+    }
+
+    let mut level: Vec<[u8; 32]> = sorted
+        .iter()
+        .map(|b| Sha256Backend::hash_bytes(b.as_byte_array()))
+        .collect();
+    while level.len() > 1 {
+        level = reduce_level(&level);
+    }
+    hex::encode(level[0])
+}
+
+/// Sibling path proving `target` is one of the bond ids committed to by
+/// [`graph_root`]. Each entry is `(sibling_hash, sibling_is_left)`.
+/// Returns `None` if `target` is not among `bonds`.
+pub fn graph_proof(bonds: &[BondId], target: BondId) -> Option<Vec<([u8; 32], bool)>> {
+    let mut sorted: Vec<BondId> = bonds.to_vec();
+    sorted.sort_unstable();
+    let mut index = sorted.iter().position(|&b| b == target)?;
+
+    let mut level: Vec<[u8; 32]> = sorted
+        .iter()
+        .map(|b| Sha256Backend::hash_bytes(b.as_byte_array()))
+        .collect();
+    let mut path = Vec::new();
+
+    while level.len() > 1 {
+        let mut padded = level.clone();
+        if padded.len() % 2 == 1 {
+            padded.push(*padded.last().unwrap());
+        }
+        let sibling_idx = if index % 2 == 0 { index + 1 } else { index - 1 };
+        let sibling_is_left = index % 2 == 1;
+        path.push((padded[sibling_idx], sibling_is_left));
+
+        level = reduce_level(&level);
+        index /= 2;
+    }
+
+    Some(path)
+}
+
+/// Verify a [`graph_proof`] sibling path for `target_bond_id` against
+/// `root_hex`.
+pub fn verify_graph_proof(root_hex: &str, target_bond_id: BondId, path: &[([u8; 32], bool)]) -> bool {
+    let root = match hex::decode(root_hex) {
+        Ok(bytes) if bytes.len() == 32 => bytes,
+        _ => return false,
+    };
+
+    let mut chain = Sha256Backend::hash_bytes(target_bond_id.as_byte_array());
+    for &(sibling, sibling_is_left) in path {
+        chain = if sibling_is_left {
+            Sha256Backend::hash_dyad(&sibling, &chain)
+        } else {
+            Sha256Backend::hash_dyad(&chain, &sibling)
+        };
+    }
+
+    chain.as_slice() == root.as_slice()
 }
 
 /// Invariant Metrics: (Weight, Depth, Leaves, ShapeHash)
@@ -126,25 +487,100 @@ mod tests {
 
     #[test]
     fn test_determinism() {
-        let h1 = get_token_hash_hex("intelligence");
-        let h2 = get_token_hash_hex("intelligence");
+        let h1 = get_token_hash_hex::<Sha256Backend>("intelligence");
+        let h2 = get_token_hash_hex::<Sha256Backend>("intelligence");
         assert_eq!(h1, h2);
     }
 
     #[test]
     fn test_different_inputs() {
-        let h1 = get_token_hash_hex("cat");
-        let h2 = get_token_hash_hex("dog");
+        let h1 = get_token_hash_hex::<Sha256Backend>("cat");
+        let h2 = get_token_hash_hex::<Sha256Backend>("dog");
         assert_ne!(h1, h2);
     }
 
     #[test]
     fn test_bond_id() {
-        let b1 = bond_id("a", "b", "IMP");
-        let b2 = bond_id("a", "b", "IMP");
+        let b1 = bond_id::<Sha256Backend>("a", "b", "IMP");
+        let b2 = bond_id::<Sha256Backend>("a", "b", "IMP");
         assert_eq!(b1, b2);
-        
-        let b3 = bond_id("b", "a", "IMP");
+
+        let b3 = bond_id::<Sha256Backend>("b", "a", "IMP");
         assert_ne!(b1, b3); // Order matters
     }
+
+    #[test]
+    fn test_backends_diverge() {
+        let sha = get_token_hash_hex::<Sha256Backend>("intelligence");
+        let blake = get_token_hash_hex::<Blake2bBackend>("intelligence");
+        let sha2d = get_token_hash_hex::<Sha256dBackend>("intelligence");
+        assert_ne!(sha, blake);
+        assert_ne!(sha, sha2d);
+        assert_ne!(blake, sha2d);
+    }
+
+    #[test]
+    fn test_token_proof_roundtrip() {
+        let s = "intelligence";
+        let root_hex = get_token_hash_hex::<Sha256Backend>(s);
+        for (index, &byte) in s.as_bytes().iter().enumerate() {
+            let proof = token_proof(s, index);
+            assert!(verify_token_proof(&root_hex, index, byte, &proof));
+        }
+    }
+
+    #[test]
+    fn test_token_proof_rejects_wrong_byte() {
+        let s = "intelligence";
+        let root_hex = get_token_hash_hex::<Sha256Backend>(s);
+        let proof = token_proof(s, 0);
+        assert!(!verify_token_proof(&root_hex, 0, b'x', &proof));
+    }
+
+    #[test]
+    fn test_graph_root_order_independent() {
+        let bonds = vec![
+            bond_id::<Sha256Backend>("a", "b", "IMP"),
+            bond_id::<Sha256Backend>("b", "c", "IMP"),
+            bond_id::<Sha256Backend>("c", "d", "IMP"),
+        ];
+        let reversed: Vec<BondId> = bonds.iter().rev().cloned().collect();
+        assert_eq!(graph_root(&bonds), graph_root(&reversed));
+    }
+
+    #[test]
+    fn test_graph_proof_roundtrip() {
+        let bonds: Vec<BondId> = ["a", "b", "c", "d", "e"]
+            .iter()
+            .map(|x| bond_id::<Sha256Backend>(x, "next", "IMP"))
+            .collect();
+        let root = graph_root(&bonds);
+        for &target in &bonds {
+            let path = graph_proof(&bonds, target).expect("target is in bonds");
+            assert!(verify_graph_proof(&root, target, &path));
+        }
+    }
+
+    #[test]
+    fn test_graph_proof_missing_target() {
+        let bonds = vec![bond_id::<Sha256Backend>("a", "b", "IMP")];
+        let not_a_bond = bond_id::<Sha256Backend>("not", "a", "bond");
+        assert!(graph_proof(&bonds, not_a_bond).is_none());
+    }
+
+    #[test]
+    fn test_token_hash_hex_roundtrip() {
+        let hash = get_token_hash::<Sha256Backend>("intelligence");
+        let hex = hash.to_hex();
+        let parsed: TokenHash = hex.parse().unwrap();
+        assert_eq!(hash, parsed);
+    }
+
+    #[test]
+    fn test_bond_id_hex_roundtrip() {
+        let id = bond_id::<Sha256Backend>("a", "b", "IMP");
+        let hex = id.to_hex();
+        let parsed: BondId = hex.parse().unwrap();
+        assert_eq!(id, parsed);
+    }
 }
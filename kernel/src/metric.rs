@@ -0,0 +1,127 @@
+//! Distance metric selection for crystallization.
+//!
+//! The brute-force (`start_crystal`) and HNSW (`hnsw_crystal`) paths must
+//! agree on what "similar" means for a given metric and threshold, so the
+//! metric and its normalization live here as the single source of truth
+//! both paths score against.
+
+/// Metric used to score a pair of vectors during crystallization. Every
+/// variant's [`score`](Metric::score) is on a "higher means more similar"
+/// scale, so a single `score > threshold` comparison works uniformly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Metric {
+    /// Normalized dot product — magnitude-independent similarity in `[-1, 1]`.
+    Cosine,
+    /// Raw, unnormalized dot product — sensitive to vector magnitude.
+    DotProduct,
+    /// Negative Euclidean (L2) distance, so higher still means "closer".
+    Euclidean,
+    /// Angular similarity `1 - acos(cosine) / π`, in `[0, 1]`.
+    Angular,
+}
+
+impl Metric {
+    /// Score a pair of raw (unnormalized) vectors under this metric.
+    pub fn score(&self, a: &[f32], b: &[f32]) -> f32 {
+        match self {
+            Metric::Cosine => cosine_similarity(a, b),
+            Metric::DotProduct => dot(a, b),
+            Metric::Euclidean => -euclidean_distance(a, b),
+            Metric::Angular => angular_similarity(a, b),
+        }
+    }
+}
+
+/// Error parsing a [`Metric`] from an unrecognized name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownMetricError;
+
+impl std::fmt::Display for UnknownMetricError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("unknown metric name")
+    }
+}
+
+impl std::error::Error for UnknownMetricError {}
+
+impl std::str::FromStr for Metric {
+    type Err = UnknownMetricError;
+
+    /// Parse a metric from its Python-facing name.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "cosine" => Ok(Metric::Cosine),
+            "dot" | "dot_product" => Ok(Metric::DotProduct),
+            "euclidean" => Ok(Metric::Euclidean),
+            "angular" => Ok(Metric::Angular),
+            _ => Err(UnknownMetricError),
+        }
+    }
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
+}
+
+fn norm(a: &[f32]) -> f32 {
+    dot(a, a).sqrt()
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let denom = norm(a) * norm(b);
+    if denom == 0.0 {
+        0.0
+    } else {
+        dot(a, b) / denom
+    }
+}
+
+fn euclidean_distance(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b.iter()).map(|(x, y)| (x - y).powi(2)).sum::<f32>().sqrt()
+}
+
+fn angular_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let cos = cosine_similarity(a, b).clamp(-1.0, 1.0);
+    1.0 - cos.acos() / std::f32::consts::PI
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cosine_ignores_magnitude() {
+        let a = vec![1.0, 0.0];
+        let b = vec![5.0, 0.0];
+        assert!((Metric::Cosine.score(&a, &b) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_dot_product_sensitive_to_magnitude() {
+        let a = vec![1.0, 0.0];
+        let b = vec![5.0, 0.0];
+        assert!((Metric::DotProduct.score(&a, &b) - 5.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_euclidean_identical_vectors() {
+        let a = vec![1.0, 2.0, 3.0];
+        assert!((Metric::Euclidean.score(&a, &a) - 0.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_angular_identical_vectors() {
+        let a = vec![1.0, 2.0, 3.0];
+        // `acos`'s derivative blows up near an argument of 1.0, so the tiny
+        // f32 rounding error in `cosine_similarity` for identical vectors
+        // (cos ~= 1.0 - 1e-7) is amplified to ~1e-4 in the angular score;
+        // a tighter tolerance here is not achievable in f32.
+        assert!((Metric::Angular.score(&a, &a) - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_from_str_rejects_unknown_name() {
+        assert!("not-a-metric".parse::<Metric>().is_err());
+        assert_eq!("cosine".parse::<Metric>(), Ok(Metric::Cosine));
+    }
+}